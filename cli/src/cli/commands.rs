@@ -1,11 +1,12 @@
-use crate::client::http::HttpClient;
+use crate::client::http::{ApiError, HttpClient};
 use crate::format::search::format_search_results;
 use crate::types::api::{IndexPathRequest, SearchParams};
 use crate::utils::path::to_absolute;
-use std::error::Error;
+use crate::OutputFormat;
+use std::io::Read;
 
-pub async fn execute_index(client: &HttpClient, path: String, force: bool) -> Result<(), Box<dyn Error>> {
-    let abs_path = to_absolute(&path)?;
+pub async fn execute_index(client: &HttpClient, path: String, force: bool) -> Result<(), ApiError> {
+    let abs_path = to_absolute(&path).map_err(|e| ApiError::Request(e.to_string()))?;
     let request = IndexPathRequest {
         path: abs_path,
         force,
@@ -19,26 +20,81 @@ pub async fn execute_index(client: &HttpClient, path: String, force: bool) -> Re
 pub async fn execute_search(
     client: &HttpClient,
     path: String,
-    query: String,
+    query: Option<String>,
+    queries: Option<String>,
     limit: u32,
     extensions: Option<String>,
-) -> Result<(), Box<dyn Error>> {
-    let abs_path = to_absolute(&path)?;
-    let params = SearchParams {
-        path: abs_path,
-        query,
-        limit,
-        extensions,
-    };
+    offset: u32,
+    format: OutputFormat,
+    highlight: bool,
+    crop: Option<usize>,
+) -> Result<(), ApiError> {
+    let abs_path = to_absolute(&path).map_err(|e| ApiError::Request(e.to_string()))?;
+    let query_list = collect_queries(query, queries).map_err(|e| ApiError::Request(e.to_string()))?;
+    let grouped = query_list.len() > 1;
+    let machine_readable = matches!(format, OutputFormat::Json | OutputFormat::Ndjson);
+
+    if grouped && format == OutputFormat::Json {
+        return Err(ApiError::Request(
+            "--format json emits one top-level object per query; use --format ndjson for batch search".to_string(),
+        ));
+    }
+
+    for query in &query_list {
+        if grouped && !machine_readable {
+            println!("# query: {}\n", query);
+        }
+
+        let params = SearchParams {
+            path: abs_path.clone(),
+            query: query.clone(),
+            limit,
+            extensions: extensions.clone(),
+            offset,
+        };
+
+        let response = client.get("/api/search/", &params).await?;
+        let formatted = format_search_results(&response, format, query, highlight, crop, offset)
+            .map_err(|e| ApiError::Request(e.to_string()))?;
+        print!("{}", formatted);
+    }
 
-    let response = client.get("/api/search/", &params).await?;
-    let formatted = format_search_results(&response)?;
-    print!("{}", formatted);
     Ok(())
 }
 
-pub async fn execute_unindex(client: &HttpClient, path: String) -> Result<(), Box<dyn Error>> {
-    let abs_path = to_absolute(&path)?;
+/// Resolves the queries to run: either the single positional `query`, or one query per
+/// non-empty, non-comment line read from `queries_source` (a file path, or `-` for stdin).
+fn collect_queries(query: Option<String>, queries_source: Option<String>) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    if let Some(source) = queries_source {
+        let contents = if source == "-" {
+            let mut buf = String::new();
+            std::io::stdin().read_to_string(&mut buf)?;
+            buf
+        } else {
+            std::fs::read_to_string(&source)?
+        };
+
+        let queries: Vec<String> = contents
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.to_string())
+            .collect();
+
+        if queries.is_empty() {
+            return Err("no queries found in --queries input".into());
+        }
+
+        Ok(queries)
+    } else if let Some(query) = query {
+        Ok(vec![query])
+    } else {
+        Err("either a query argument or --queries must be provided".into())
+    }
+}
+
+pub async fn execute_unindex(client: &HttpClient, path: String) -> Result<(), ApiError> {
+    let abs_path = to_absolute(&path).map_err(|e| ApiError::Request(e.to_string()))?;
     let request = IndexPathRequest {
         path: abs_path,
         force: false, // force is not used for unindex
@@ -47,4 +103,4 @@ pub async fn execute_unindex(client: &HttpClient, path: String) -> Result<(), Bo
     let response = client.delete("/api/index/", &request).await?;
     println!("{}", response);
     Ok(())
-}
\ No newline at end of file
+}
@@ -1,43 +1,145 @@
-use reqwest;
-use serde::Serialize;
-use std::error::Error;
-use std::time::Duration;
+use rand::Rng;
+use reqwest::{self, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
 
-const BASE_URL: &str = "http://localhost:19531";
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// A server error code mapped to a human message. Modeled on MeiliSearch's
+/// `Code`/`ErrCode` pairing.
+#[derive(Debug, Clone)]
+pub struct ErrCode {
+    pub code: &'static str,
+    pub message: &'static str,
+}
+
+/// Maps a server-returned error code string to its `ErrCode`. Returns `None`
+/// for unrecognized codes so callers can fall back to the server's own code
+/// string instead of collapsing it, keeping it scriptable against new codes
+/// the server may add later.
+fn lookup_code(code_str: &str) -> Option<ErrCode> {
+    match code_str {
+        "index_not_found" => Some(ErrCode {
+            code: "index_not_found",
+            message: "The requested index could not be found",
+        }),
+        "invalid_index_uid" => Some(ErrCode {
+            code: "invalid_index_uid",
+            message: "The index identifier is invalid",
+        }),
+        "missing_primary_key" => Some(ErrCode {
+            code: "missing_primary_key",
+            message: "The index is missing a primary key",
+        }),
+        "index_not_accessible" => Some(ErrCode {
+            code: "index_not_accessible",
+            message: "The index could not be accessed",
+        }),
+        _ => None,
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct ServerErrorBody {
+    code: Option<String>,
+    message: Option<String>,
+    #[serde(rename = "type")]
+    #[allow(dead_code)]
+    error_type: Option<String>,
+}
+
+/// Errors produced while talking to the indexing server.
+#[derive(Debug)]
+pub enum ApiError {
+    /// The server responded with a non-2xx status. `code` and `message` come from the JSON
+    /// error body when it carries a code (unknown codes are kept as sent); otherwise `code`
+    /// is `internal` and `message` names the status.
+    Server {
+        code: String,
+        message: String,
+        status: StatusCode,
+    },
+    /// The server could not be reached at all (connection refused, timeout, DNS, ...).
+    Unreachable(String),
+    /// Any other request-level failure (serialization, unparsable response body, ...).
+    Request(String),
+}
+
+impl ApiError {
+    /// Exit code the CLI should use for this error: `1` for user-actionable
+    /// errors (bad input, missing resource, a local failure before any
+    /// request was sent), `2` when the server itself is unavailable or
+    /// failing.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ApiError::Server { status, .. } if status.is_client_error() => 1,
+            ApiError::Server { .. } => 2,
+            ApiError::Unreachable(_) => 2,
+            ApiError::Request(_) => 1,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Server { message, code, .. } => write!(f, "{} ({})", message, code),
+            ApiError::Unreachable(msg) => write!(f, "server unavailable: {}", msg),
+            ApiError::Request(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_connect() || err.is_timeout() {
+            ApiError::Unreachable(err.to_string())
+        } else {
+            ApiError::Request(err.to_string())
+        }
+    }
+}
+
+impl From<serde_json::Error> for ApiError {
+    fn from(err: serde_json::Error) -> Self {
+        ApiError::Request(err.to_string())
+    }
+}
 
 pub struct HttpClient {
     client: reqwest::Client,
+    base_url: String,
+    max_retries: u32,
 }
 
 impl HttpClient {
-    pub fn new() -> Self {
+    pub fn new(base_url: String) -> Self {
         let client = reqwest::Client::builder()
             .timeout(Duration::from_secs(30))
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { client }
-    }
-
-    pub async fn post<T: Serialize>(&self, path: &str, body: &T) -> Result<String, Box<dyn Error>> {
-        let url = format!("{}{}", BASE_URL, path);
-        let response = self
-            .client
-            .post(&url)
-            .json(body)
-            .send()
-            .await?;
+        let max_retries = std::env::var("CODE_MAX_RETRIES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MAX_RETRIES);
 
-        if !response.status().is_success() {
-            return Err(format!("Request failed with status: {}", response.status()).into());
-        }
+        Self { client, base_url, max_retries }
+    }
 
-        let text = response.text().await?;
-        Ok(text)
+    pub async fn post<T: Serialize>(&self, path: &str, body: &T) -> Result<String, ApiError> {
+        let url = format!("{}{}", self.base_url, path);
+        self.send_with_retry("POST", &url, || self.client.post(&url).json(body)).await
     }
 
-    pub async fn get<T: Serialize>(&self, path: &str, params: &T) -> Result<String, Box<dyn Error>> {
-        let url = format!("{}{}", BASE_URL, path);
+    pub async fn get<T: Serialize>(&self, path: &str, params: &T) -> Result<String, ApiError> {
+        let url = format!("{}{}", self.base_url, path);
 
         // Convert params to query string using serde_json
         let params_json = serde_json::to_value(params)?;
@@ -59,30 +161,155 @@ impl HttpClient {
             url
         };
 
-        let response = self.client.get(&full_url).send().await?;
+        self.send_with_retry("GET", &full_url, || self.client.get(&full_url)).await
+    }
 
-        if !response.status().is_success() {
-            return Err(format!("Request failed with status: {}", response.status()).into());
+    pub async fn delete<T: Serialize>(&self, path: &str, body: &T) -> Result<String, ApiError> {
+        let url = format!("{}{}", self.base_url, path);
+        self.send_with_retry("DELETE", &url, || self.client.delete(&url).json(body)).await
+    }
+
+    /// Sends a request built by `build`, retrying on connection errors and 5xx/429 responses
+    /// with exponential backoff plus jitter, up to `max_retries` times. Each attempt is logged
+    /// in a tracing span (method, url, attempt, final status/latency) that only produces output
+    /// when the CLI is run with `--verbose`.
+    async fn send_with_retry<F>(&self, method: &str, url: &str, build: F) -> Result<String, ApiError>
+    where
+        F: Fn() -> reqwest::RequestBuilder,
+    {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let span = tracing::info_span!("http_request", method, url, attempt);
+            let _enter = span.enter();
+            let start = Instant::now();
+
+            match build().send().await {
+                Ok(response) => {
+                    let status = response.status();
+                    info!(status = %status, latency_ms = start.elapsed().as_millis(), "request completed");
+
+                    if should_retry_status(status) && attempt <= self.max_retries {
+                        let delay = backoff_delay(attempt);
+                        warn!(status = %status, attempt, delay_ms = delay.as_millis(), "retrying after server error");
+                        tokio::time::sleep(delay).await;
+                        continue;
+                    }
+
+                    return Self::handle_response(response).await;
+                }
+                Err(err) => {
+                    if is_retryable(&err) && attempt <= self.max_retries {
+                        let delay = backoff_delay(attempt);
+                        warn!(error = %err, attempt, delay_ms = delay.as_millis(), "retrying after connection error");
+                        tokio::time::sleep(delay).await;
+                        continue;
+                    }
+
+                    return Err(ApiError::from(err));
+                }
+            }
+        }
+    }
+
+    /// Checks the response status, parsing the JSON error body (`{"code", "message", "type"}`)
+    /// into a typed `ApiError::Server` on failure instead of collapsing it to a flat string.
+    async fn handle_response(response: reqwest::Response) -> Result<String, ApiError> {
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            let parsed = serde_json::from_str::<ServerErrorBody>(&text).ok();
+
+            let (code, message) = match parsed.and_then(|body| body.code.map(|c| (c, body.message))) {
+                Some((code_str, message)) => match lookup_code(&code_str) {
+                    Some(info) => (info.code.to_string(), message.unwrap_or_else(|| info.message.to_string())),
+                    None => (code_str, message.unwrap_or_else(|| "An unexpected server error occurred".to_string())),
+                },
+                None => ("internal".to_string(), format!("request failed with status: {}", status)),
+            };
+
+            return Err(ApiError::Server { code, message, status });
         }
 
         let text = response.text().await?;
         Ok(text)
     }
+}
+
+fn should_retry_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
 
-    pub async fn delete<T: Serialize>(&self, path: &str, body: &T) -> Result<String, Box<dyn Error>> {
-        let url = format!("{}{}", BASE_URL, path);
-        let response = self
-            .client
-            .delete(&url)
-            .json(body)
-            .send()
-            .await?;
+fn is_retryable(err: &reqwest::Error) -> bool {
+    err.is_connect() || err.is_timeout()
+}
 
-        if !response.status().is_success() {
-            return Err(format!("Request failed with status: {}", response.status()).into());
+/// Exponential backoff with jitter: `base * 2^(attempt - 1)`, capped, plus up to 25% jitter.
+fn backoff_delay(attempt: u32) -> Duration {
+    let exp = RETRY_BASE_DELAY.saturating_mul(1u32 << attempt.saturating_sub(1).min(10));
+    let capped = exp.min(RETRY_MAX_DELAY);
+    let jitter_ms = rand::thread_rng().gen_range(0..=capped.as_millis() as u64 / 4 + 1);
+    capped + Duration::from_millis(jitter_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_delay_doubles_each_attempt_before_the_cap() {
+        let base_ms = RETRY_BASE_DELAY.as_millis();
+        for attempt in 1..=3 {
+            let delay = backoff_delay(attempt);
+            let unjittered = base_ms * (1u128 << (attempt - 1));
+            let max_jitter = unjittered / 4 + 1;
+            assert!(delay.as_millis() >= unjittered, "attempt {attempt}: {delay:?}");
+            assert!(delay.as_millis() <= unjittered + max_jitter, "attempt {attempt}: {delay:?}");
         }
+    }
 
-        let text = response.text().await?;
-        Ok(text)
+    #[test]
+    fn backoff_delay_never_exceeds_max_plus_jitter() {
+        let max_ms = RETRY_MAX_DELAY.as_millis();
+        for attempt in [10, 20, u32::MAX] {
+            let delay = backoff_delay(attempt);
+            let max_jitter = max_ms / 4 + 1;
+            assert!(delay.as_millis() >= max_ms, "attempt {attempt}: {delay:?}");
+            assert!(delay.as_millis() <= max_ms + max_jitter, "attempt {attempt}: {delay:?}");
+        }
+    }
+
+    #[test]
+    fn exit_code_reserves_2_for_server_unreachable_and_5xx() {
+        assert_eq!(ApiError::Unreachable("down".to_string()).exit_code(), 2);
+        assert_eq!(
+            ApiError::Server {
+                code: "internal".to_string(),
+                message: "boom".to_string(),
+                status: StatusCode::INTERNAL_SERVER_ERROR,
+            }
+            .exit_code(),
+            2
+        );
+    }
+
+    #[test]
+    fn exit_code_is_1_for_user_actionable_errors() {
+        assert_eq!(ApiError::Request("missing --queries file".to_string()).exit_code(), 1);
+        assert_eq!(
+            ApiError::Server {
+                code: "invalid_index_uid".to_string(),
+                message: "bad uid".to_string(),
+                status: StatusCode::BAD_REQUEST,
+            }
+            .exit_code(),
+            1
+        );
+    }
+
+    #[test]
+    fn lookup_code_preserves_unknown_server_code() {
+        assert!(lookup_code("index_not_found").is_some());
+        assert!(lookup_code("some_brand_new_code").is_none());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,133 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::PathBuf;
+
+const DEFAULT_SERVER_URL: &str = "http://localhost:19531";
+
+#[derive(Deserialize, Debug, Default)]
+struct Config {
+    default_profile: Option<String>,
+    #[serde(default)]
+    profiles: HashMap<String, Profile>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Profile {
+    url: String,
+}
+
+/// Resolves the indexing server's base URL, in priority order: the `--server` flag, the
+/// `CODE_SERVER` env var, a named profile (`--profile`, or the config's `default_profile`)
+/// from `~/.config/code/config.toml`, and finally the built-in default.
+pub fn resolve_server_url(server: Option<String>, profile: Option<String>) -> Result<String, Box<dyn Error>> {
+    let env_server = std::env::var("CODE_SERVER").ok();
+    let config = load_config()?;
+    resolve(server, env_server, profile, config)
+}
+
+/// Priority-order resolution logic, separated from env/file I/O so it can be tested directly.
+fn resolve(
+    server: Option<String>,
+    env_server: Option<String>,
+    profile: Option<String>,
+    config: Option<Config>,
+) -> Result<String, Box<dyn Error>> {
+    if let Some(url) = server {
+        return Ok(url);
+    }
+
+    if let Some(url) = env_server {
+        return Ok(url);
+    }
+
+    let profile_name = profile.or_else(|| config.as_ref().and_then(|c| c.default_profile.clone()));
+    if let Some(name) = profile_name {
+        let config = config.ok_or_else(|| format!("no config file found at {}", config_path().display()))?;
+        let found = config
+            .profiles
+            .get(&name)
+            .ok_or_else(|| format!("unknown profile: {}", name))?;
+        return Ok(found.url.clone());
+    }
+
+    Ok(DEFAULT_SERVER_URL.to_string())
+}
+
+fn config_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_default();
+    PathBuf::from(home).join(".config/code/config.toml")
+}
+
+fn load_config() -> Result<Option<Config>, Box<dyn Error>> {
+    let path = config_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents = std::fs::read_to_string(path)?;
+    let config: Config = toml::from_str(&contents)?;
+    Ok(Some(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_profiles(default_profile: Option<&str>, profiles: &[(&str, &str)]) -> Config {
+        Config {
+            default_profile: default_profile.map(str::to_string),
+            profiles: profiles
+                .iter()
+                .map(|(name, url)| (name.to_string(), Profile { url: url.to_string() }))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn server_flag_wins_over_everything() {
+        let config = config_with_profiles(Some("default"), &[("default", "http://config")]);
+        let url = resolve(
+            Some("http://flag".to_string()),
+            Some("http://env".to_string()),
+            Some("default".to_string()),
+            Some(config),
+        )
+        .unwrap();
+        assert_eq!(url, "http://flag");
+    }
+
+    #[test]
+    fn env_var_wins_over_profile() {
+        let config = config_with_profiles(Some("default"), &[("default", "http://config")]);
+        let url = resolve(None, Some("http://env".to_string()), None, Some(config)).unwrap();
+        assert_eq!(url, "http://env");
+    }
+
+    #[test]
+    fn explicit_profile_wins_over_default_profile() {
+        let config = config_with_profiles(Some("a"), &[("a", "http://a"), ("b", "http://b")]);
+        let url = resolve(None, None, Some("b".to_string()), Some(config)).unwrap();
+        assert_eq!(url, "http://b");
+    }
+
+    #[test]
+    fn falls_back_to_config_default_profile() {
+        let config = config_with_profiles(Some("a"), &[("a", "http://a")]);
+        let url = resolve(None, None, None, Some(config)).unwrap();
+        assert_eq!(url, "http://a");
+    }
+
+    #[test]
+    fn unknown_profile_is_an_error() {
+        let config = config_with_profiles(None, &[("a", "http://a")]);
+        let err = resolve(None, None, Some("missing".to_string()), Some(config));
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn falls_back_to_default_url_with_no_config() {
+        let url = resolve(None, None, None, None).unwrap();
+        assert_eq!(url, DEFAULT_SERVER_URL);
+    }
+}
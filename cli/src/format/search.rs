@@ -1,45 +1,300 @@
 use crate::types::api::{SearchResponse, SearchResult};
+use crate::OutputFormat;
+use serde::Serialize;
 use serde_json;
 use std::error::Error;
 
-pub fn format_search_results(data: &str) -> Result<String, Box<dyn Error>> {
-    // Try to parse as SearchResponse first
-    if let Ok(response) = serde_json::from_str::<SearchResponse>(data) {
-        if let Some(results) = response.results {
-            return format_results(&results);
+pub fn format_search_results(
+    data: &str,
+    format: OutputFormat,
+    query: &str,
+    highlight: bool,
+    crop: Option<usize>,
+    offset: u32,
+) -> Result<String, Box<dyn Error>> {
+    let mut results = parse_results(data)?;
+    let terms = query_terms(query);
+
+    let json_markers = matches!(format, OutputFormat::Json | OutputFormat::Ndjson);
+    for result in &mut results {
+        if let Some(n) = crop {
+            let center = first_match_offset(&result.content, &terms);
+            result.content = crop_content(&result.content, center, n);
+        }
+        if highlight {
+            result.content = highlight_content(&result.content, &terms, json_markers);
         }
     }
 
-    // Try to parse as single SearchResult
-    if let Ok(result) = serde_json::from_str::<SearchResult>(data) {
-        return format_results(&[result]);
+    match format {
+        OutputFormat::Text => format_text(&results, offset),
+        OutputFormat::Json => format_json(&results),
+        OutputFormat::Ndjson => format_ndjson(&results),
     }
+}
+
+/// Splits a query into the whitespace-separated terms used for highlighting/cropping.
+fn query_terms(query: &str) -> Vec<String> {
+    query
+        .split_whitespace()
+        .map(|s| s.to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Returns the byte offset of the earliest case-insensitive match of any term in `content`.
+fn first_match_offset(content: &str, terms: &[String]) -> Option<usize> {
+    terms
+        .iter()
+        .filter(|term| !term.is_empty())
+        .filter_map(|term| ci_find_all(content, term).first().map(|&(start, _)| start))
+        .min()
+}
+
+/// Finds all occurrences of `term` in `content`, matching case-insensitively against the
+/// original bytes so returned ranges always index into `content` (not a lowercased copy,
+/// whose byte length can differ from the original for characters like `İ`).
+fn ci_find_all(content: &str, term: &str) -> Vec<(usize, usize)> {
+    let mut matches = Vec::new();
+    let chars: Vec<(usize, char)> = content.char_indices().collect();
+
+    for start in 0..chars.len() {
+        let start_byte = chars[start].0;
+        let mut buf = String::new();
+        let mut end_byte = start_byte;
+
+        for &(byte_pos, ch) in &chars[start..] {
+            end_byte = byte_pos + ch.len_utf8();
+            for lc in ch.to_lowercase() {
+                buf.push(lc);
+            }
+            if buf.len() >= term.len() {
+                break;
+            }
+        }
 
-    // Try to parse as array of SearchResults
-    if let Ok(results) = serde_json::from_str::<Vec<SearchResult>>(data) {
-        return format_results(&results);
+        if buf == term {
+            matches.push((start_byte, end_byte));
+        }
     }
 
-    Err("Failed to parse search response".into())
+    matches
 }
 
-fn format_results(results: &[SearchResult]) -> Result<String, Box<dyn Error>> {
-    let mut output = String::new();
+/// Finds all case-insensitive occurrences of `terms` in `content` as merged, non-overlapping
+/// byte ranges.
+fn find_matches(content: &str, terms: &[String]) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
 
-    for result in results {
-        let mut content = result.content.clone();
-        // Replace escaped newlines with actual newlines (matching Go implementation)
-        content = content.replace("\\r\\n", "\r\n");
-        content = content.replace("\\n", "\n");
+    for term in terms {
+        if term.is_empty() {
+            continue;
+        }
+        ranges.extend(ci_find_all(content, term));
+    }
+
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in ranges {
+        if let Some(last) = merged.last_mut() {
+            if start <= last.1 {
+                last.1 = last.1.max(end);
+                continue;
+            }
+        }
+        merged.push((start, end));
+    }
+
+    merged
+}
+
+/// Wraps each matched term in `content` with highlight markers: ANSI bold for tty (text) output,
+/// `<em>...</em>` for JSON/NDJSON output.
+fn highlight_content(content: &str, terms: &[String], json_markers: bool) -> String {
+    let matches = find_matches(content, terms);
+    if matches.is_empty() {
+        return content.to_string();
+    }
+
+    let (open, close) = if json_markers {
+        ("<em>", "</em>")
+    } else {
+        ("\x1b[1m", "\x1b[0m")
+    };
+
+    let mut output = String::with_capacity(content.len() + matches.len() * (open.len() + close.len()));
+    let mut cursor = 0;
+    for (start, end) in matches {
+        output.push_str(&content[cursor..start]);
+        output.push_str(open);
+        output.push_str(&content[start..end]);
+        output.push_str(close);
+        cursor = end;
+    }
+    output.push_str(&content[cursor..]);
+
+    output
+}
+
+/// Crops `content` to a window of roughly `crop_len` characters centered on `center_byte`
+/// (or the start of the content if no match was found).
+fn crop_content(content: &str, center_byte: Option<usize>, crop_len: usize) -> String {
+    // Byte offset of each char, plus a trailing sentinel for `content.len()`, so char index
+    // `i` always maps to `boundaries[i]` regardless of how many bytes that char takes.
+    let boundaries: Vec<usize> = content
+        .char_indices()
+        .map(|(pos, _)| pos)
+        .chain(std::iter::once(content.len()))
+        .collect();
+    let num_chars = boundaries.len() - 1;
+
+    if num_chars <= crop_len {
+        return content.to_string();
+    }
+
+    let center_char = center_byte
+        .map(|byte| boundaries.partition_point(|&b| b <= byte).saturating_sub(1))
+        .unwrap_or(0);
+
+    let half = crop_len / 2;
+    let mut start_char = center_char.saturating_sub(half);
+    let mut end_char = (start_char + crop_len).min(num_chars);
+    if end_char == num_chars {
+        start_char = end_char.saturating_sub(crop_len);
+    }
+
+    let start = boundaries[start_char];
+    let end = boundaries[end_char];
+
+    let mut result = String::new();
+    if start > 0 {
+        result.push('…');
+    }
+    result.push_str(&content[start..end]);
+    if end < content.len() {
+        result.push('…');
+    }
 
+    result
+}
+
+/// Parses the raw server response into results, unescaping `content` along the way
+/// so every output format sees real newlines instead of the wire-escaped form.
+fn parse_results(data: &str) -> Result<Vec<SearchResult>, Box<dyn Error>> {
+    let mut results = if let Ok(response) = serde_json::from_str::<SearchResponse>(data) {
+        response.results.unwrap_or_default()
+    } else if let Ok(result) = serde_json::from_str::<SearchResult>(data) {
+        vec![result]
+    } else if let Ok(results) = serde_json::from_str::<Vec<SearchResult>>(data) {
+        results
+    } else {
+        return Err("Failed to parse search response".into());
+    };
+
+    for result in &mut results {
+        result.content = unescape_content(&result.content);
+    }
+
+    Ok(results)
+}
+
+fn unescape_content(content: &str) -> String {
+    // Replace escaped newlines with actual newlines (matching Go implementation)
+    content.replace("\\r\\n", "\r\n").replace("\\n", "\n")
+}
+
+fn format_text(results: &[SearchResult], offset: u32) -> Result<String, Box<dyn Error>> {
+    let mut output = String::new();
+
+    for (position, result) in results.iter().enumerate() {
+        output.push_str(&format!("index: {}\n", offset as usize + position));
         output.push_str(&format!("file: {}\n", result.file));
         output.push_str(&format!("startLine: {}\n", result.start_line));
         output.push_str(&format!("endLine: {}\n", result.end_line));
         output.push_str(&format!("score: {}\n", result.score));
         output.push_str(&format!("language: {}\n\n", result.language));
-        output.push_str(&content);
+        output.push_str(&result.content);
         output.push_str("\n\n---\n");
     }
 
     Ok(output)
-}
\ No newline at end of file
+}
+
+#[derive(Serialize)]
+struct JsonSearchOutput<'a> {
+    results: &'a [SearchResult],
+    count: usize,
+}
+
+fn format_json(results: &[SearchResult]) -> Result<String, Box<dyn Error>> {
+    let output = JsonSearchOutput {
+        results,
+        count: results.len(),
+    };
+    Ok(serde_json::to_string_pretty(&output)?)
+}
+
+fn format_ndjson(results: &[SearchResult]) -> Result<String, Box<dyn Error>> {
+    let mut output = String::new();
+    for result in results {
+        output.push_str(&serde_json::to_string(result)?);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ci_find_all_matches_multibyte_case_folding() {
+        // "İ" (U+0130) lowercases to the two-char sequence "i̇", which is longer in
+        // bytes than the original character.
+        let content = "İstanbul code";
+        let matches = ci_find_all(content, "i̇stanbul");
+        assert_eq!(matches, vec![(0, "İstanbul".len())]);
+        assert_eq!(&content[matches[0].0..matches[0].1], "İstanbul");
+    }
+
+    #[test]
+    fn ci_find_all_is_case_insensitive_on_ascii() {
+        let matches = ci_find_all("Code Search", "search");
+        assert_eq!(matches, vec![(5, 11)]);
+    }
+
+    #[test]
+    fn find_matches_merges_overlapping_ranges() {
+        let ranges = find_matches("ababab", &["abab".to_string(), "baba".to_string()]);
+        assert_eq!(ranges, vec![(0, 6)]);
+    }
+
+    #[test]
+    fn find_matches_keeps_disjoint_ranges_separate() {
+        let ranges = find_matches("foo bar baz", &["foo".to_string(), "baz".to_string()]);
+        assert_eq!(ranges, vec![(0, 3), (8, 11)]);
+    }
+
+    #[test]
+    fn crop_content_returns_whole_string_when_shorter_than_window() {
+        assert_eq!(crop_content("short", Some(0), 50), "short");
+    }
+
+    #[test]
+    fn crop_content_counts_chars_not_bytes() {
+        // Each "é" is 2 bytes; a 6-char window should keep all 6 characters, not truncate
+        // partway through due to byte-length comparisons.
+        let content = "ééééééé";
+        let cropped = crop_content(content, Some(0), 6);
+        assert_eq!(cropped.chars().filter(|c| *c == 'é').count(), 6);
+        assert!(cropped.ends_with('…'));
+    }
+
+    #[test]
+    fn crop_content_at_string_end_has_no_trailing_ellipsis() {
+        let content = "0123456789";
+        let cropped = crop_content(content, Some(9), 4);
+        assert_eq!(cropped, "…6789");
+    }
+}
@@ -3,6 +3,7 @@ use client::http::HttpClient;
 
 mod cli;
 mod client;
+mod config;
 mod types;
 mod format;
 mod utils;
@@ -14,6 +15,12 @@ mod utils;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    #[arg(long, global = true, help = "Enable verbose request tracing")]
+    pub verbose: bool,
+    #[arg(long, global = true, help = "Indexing server URL (overrides CODE_SERVER and any profile)")]
+    pub server: Option<String>,
+    #[arg(long, global = true, help = "Named server profile from ~/.config/code/config.toml")]
+    pub profile: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -29,12 +36,22 @@ pub enum Commands {
     Search {
         #[arg(help = "Directory path to search in")]
         path: String,
-        #[arg(help = "Search query string")]
-        query: String,
+        #[arg(help = "Search query string (omit when using --queries)")]
+        query: Option<String>,
         #[arg(help = "Maximum number of results", default_value = "5")]
         limit: u32,
         #[arg(help = "File extensions to filter (e.g., \".go,.js\")")]
         extensions: Option<String>,
+        #[arg(long, default_value = "0", help = "Number of results to skip before returning the window")]
+        offset: u32,
+        #[arg(long, value_name = "FILE", help = "Read queries (one per line) from FILE, or \"-\" for stdin; blank lines and lines starting with # are skipped")]
+        queries: Option<String>,
+        #[arg(long, value_enum, default_value = "text", help = "Output format (text, json, ndjson)")]
+        format: OutputFormat,
+        #[arg(long, help = "Highlight matched query terms in results")]
+        highlight: bool,
+        #[arg(long, value_name = "N", help = "Crop content to a window of roughly N characters around the first match")]
+        crop: Option<usize>,
     },
     #[command(about = "Unindex a code directory", long_about = "Remove a code directory at the specified path from the index.")]
     Unindex {
@@ -43,22 +60,50 @@ pub enum Commands {
     },
 }
 
+/// Output mode for the `search` command.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-oriented `index:/file:/startLine:/...---` blocks (default).
+    Text,
+    /// A single JSON object: `{"results": [...], "count": N}`.
+    Json,
+    /// One `SearchResult` JSON object per line, for streaming consumption.
+    Ndjson,
+}
+
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() {
     let cli = Cli::parse();
-    let client = HttpClient::new();
 
-    match cli.command {
+    if cli.verbose {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::INFO)
+            .init();
+    }
+
+    let base_url = match config::resolve_server_url(cli.server.clone(), cli.profile.clone()) {
+        Ok(url) => url,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let client = HttpClient::new(base_url);
+
+    let result = match cli.command {
         Commands::Index { path, force } => {
-            cli::commands::execute_index(&client, path, force).await?;
+            cli::commands::execute_index(&client, path, force).await
         }
-        Commands::Search { path, query, limit, extensions } => {
-            cli::commands::execute_search(&client, path, query, limit, extensions).await?;
+        Commands::Search { path, query, limit, extensions, offset, queries, format, highlight, crop } => {
+            cli::commands::execute_search(&client, path, query, queries, limit, extensions, offset, format, highlight, crop).await
         }
         Commands::Unindex { path } => {
-            cli::commands::execute_unindex(&client, path).await?;
+            cli::commands::execute_unindex(&client, path).await
         }
-    }
+    };
 
-    Ok(())
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(e.exit_code());
+    }
 }
\ No newline at end of file
@@ -22,9 +22,10 @@ pub struct SearchParams {
     pub query: String,
     pub limit: u32,
     pub extensions: Option<String>,
+    pub offset: u32,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct SearchResult {
     pub file: String,
     #[serde(rename = "startLine")]